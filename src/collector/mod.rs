@@ -0,0 +1,6 @@
+mod tweaked_score_top_collector;
+
+pub use self::tweaked_score_top_collector::{
+    CustomScoreTopCollector, CustomScorer, CustomSegmentScorer, FastFieldConvertCollector,
+    FastFieldScoreTweaker, ScoreSegmentTweaker, ScoreTweaker, TweakedScoreTopCollector,
+};
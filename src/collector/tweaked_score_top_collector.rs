@@ -0,0 +1,428 @@
+use collector::Collector;
+use fastfield::FastFieldReader;
+use schema::Field;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use DocAddress;
+use DocId;
+use Result;
+use Score;
+use SegmentLocalId;
+use SegmentReader;
+
+/// `ScoreTweaker` makes it possible to tweak the original score of a
+/// document for a given segment.
+///
+/// It is typically used to combine the score returned by a query
+/// (BM25 for a term query; `RegexQuery` scores every match 1.0) with a
+/// value stored in a fast field, such as a precomputed popularity
+/// score: `final = bm25 * ln(1 + downloads)`.
+pub trait ScoreTweaker<TScore>: Sync {
+    /// The segment-local counterpart, holding whatever per-segment
+    /// state is required (typically a `FastFieldReader`).
+    type Child: ScoreSegmentTweaker<TScore>;
+
+    /// Builds a `ScoreSegmentTweaker` for a specific segment.
+    fn for_segment(&self, segment_reader: &SegmentReader) -> Result<Self::Child>;
+}
+
+/// Segment-local counterpart of `ScoreTweaker`.
+pub trait ScoreSegmentTweaker<TScore> {
+    /// Tweaks the original score for the given document, within the
+    /// segment this `ScoreSegmentTweaker` was built for.
+    fn score(&mut self, doc: DocId, score: Score) -> TScore;
+}
+
+/// `CustomScorer` is the `ScoreTweaker` variant used when the original
+/// BM25 score is irrelevant and documents should be ranked solely on a
+/// value derived from the document itself, e.g. a precomputed
+/// `crate_score` fast field.
+pub trait CustomScorer<TScore>: Sync {
+    /// The segment-local counterpart, see `CustomSegmentScorer`.
+    type Child: CustomSegmentScorer<TScore>;
+
+    /// Builds a `CustomSegmentScorer` for a specific segment.
+    fn for_segment(&self, segment_reader: &SegmentReader) -> Result<Self::Child>;
+}
+
+/// Segment-local counterpart of `CustomScorer`.
+pub trait CustomSegmentScorer<TScore> {
+    /// Computes the score for `doc`, ignoring the original BM25 score.
+    fn score(&mut self, doc: DocId) -> TScore;
+}
+
+/// Reads a fast field and converts its raw `u64` value to the
+/// `TScore` expected by a `ScoreSegmentTweaker`/`CustomSegmentScorer`,
+/// such as the `Score` the collectors of this module rank on. Both
+/// `TweakedScoreTopCollector` and `CustomScoreTopCollector` only accept
+/// `Score` as `TScore`, so their `top_docs()` returns the converted
+/// `Score` with each `DocAddress`, not the raw fast-field value.
+pub struct FastFieldConvertCollector<TScore, TConvert>
+where
+    TConvert: Fn(u64) -> TScore,
+{
+    fast_field_reader: FastFieldReader<u64>,
+    convert: TConvert,
+}
+
+impl<TScore, TConvert> FastFieldConvertCollector<TScore, TConvert>
+where
+    TConvert: Fn(u64) -> TScore,
+{
+    /// Wraps `fast_field_reader`, converting every value through `convert`.
+    pub fn new(
+        fast_field_reader: FastFieldReader<u64>,
+        convert: TConvert,
+    ) -> FastFieldConvertCollector<TScore, TConvert> {
+        FastFieldConvertCollector {
+            fast_field_reader,
+            convert,
+        }
+    }
+
+    /// Reads and converts the fast field value for `doc`.
+    pub fn convert_doc(&self, doc: DocId) -> TScore {
+        let val = self.fast_field_reader.get(doc);
+        (self.convert)(val)
+    }
+}
+
+/// A `ScoreTweaker` multiplying the original BM25 score by
+/// `ln(1 + value)`, where `value` is read from a `u64` fast field.
+///
+/// This covers the crate-search case: combining a query's score with
+/// a `monthly_downloads` (or any other popularity) fast field.
+/// `RegexQuery`/`FuzzyTermQuery` score every match 1.0, so their
+/// results end up ranked by popularity alone.
+pub struct FastFieldScoreTweaker {
+    fast_field: Field,
+}
+
+impl FastFieldScoreTweaker {
+    /// Creates a `FastFieldScoreTweaker` reading popularity from `fast_field`.
+    pub fn new(fast_field: Field) -> FastFieldScoreTweaker {
+        FastFieldScoreTweaker { fast_field }
+    }
+}
+
+impl ScoreTweaker<Score> for FastFieldScoreTweaker {
+    type Child = FastFieldConvertCollector<Score, fn(u64) -> Score>;
+
+    fn for_segment(&self, segment_reader: &SegmentReader) -> Result<Self::Child> {
+        let fast_field_reader = segment_reader.fast_field_reader::<u64>(self.fast_field)?;
+        Ok(FastFieldConvertCollector::new(
+            fast_field_reader,
+            popularity_multiplier,
+        ))
+    }
+}
+
+impl ScoreSegmentTweaker<Score> for FastFieldConvertCollector<Score, fn(u64) -> Score> {
+    fn score(&mut self, doc: DocId, score: Score) -> Score {
+        score * self.convert_doc(doc)
+    }
+}
+
+impl CustomScorer<Score> for FastFieldScoreTweaker {
+    type Child = FastFieldConvertCollector<Score, fn(u64) -> Score>;
+
+    fn for_segment(&self, segment_reader: &SegmentReader) -> Result<Self::Child> {
+        let fast_field_reader = segment_reader.fast_field_reader::<u64>(self.fast_field)?;
+        Ok(FastFieldConvertCollector::new(
+            fast_field_reader,
+            popularity_multiplier,
+        ))
+    }
+}
+
+impl CustomSegmentScorer<Score> for FastFieldConvertCollector<Score, fn(u64) -> Score> {
+    fn score(&mut self, doc: DocId) -> Score {
+        self.convert_doc(doc)
+    }
+}
+
+fn popularity_multiplier(value: u64) -> Score {
+    ((value as Score) + 1f32).ln()
+}
+
+struct ComparableDoc<TScore> {
+    score: TScore,
+    doc: DocAddress,
+}
+
+impl<TScore: PartialOrd> PartialOrd for ComparableDoc<TScore> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<TScore: PartialOrd> Ord for ComparableDoc<TScore> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .partial_cmp(&self.score)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<TScore: PartialOrd> PartialEq for ComparableDoc<TScore> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<TScore: PartialOrd> Eq for ComparableDoc<TScore> {}
+
+/// A top-K heap shared by `TweakedScoreTopCollector` and
+/// `CustomScoreTopCollector`, parameterized over how the per-document
+/// score is produced.
+struct TopKHeap<TScore> {
+    limit: usize,
+    heap: BinaryHeap<ComparableDoc<TScore>>,
+}
+
+impl<TScore: PartialOrd> TopKHeap<TScore> {
+    fn with_limit(limit: usize) -> TopKHeap<TScore> {
+        TopKHeap {
+            limit,
+            heap: BinaryHeap::with_capacity(limit),
+        }
+    }
+
+    fn collect(&mut self, doc: DocAddress, score: TScore) {
+        let comparable_doc = ComparableDoc { score, doc };
+        if self.heap.len() < self.limit {
+            self.heap.push(comparable_doc);
+        } else if let Some(mut worst) = self.heap.peek_mut() {
+            if comparable_doc < *worst {
+                *worst = comparable_doc;
+            }
+        }
+    }
+
+    fn into_top_docs(self) -> Vec<(TScore, DocAddress)> {
+        let docs: Vec<ComparableDoc<TScore>> = self.heap.into_sorted_vec();
+        docs.into_iter().map(|cd| (cd.score, cd.doc)).collect()
+    }
+}
+
+/// `TweakedScoreTopCollector` ranks documents by a score that has been
+/// tweaked by a `ScoreTweaker`, instead of the raw BM25 score handed
+/// out by the query's `Weight`.
+///
+/// A typical use case is a crate search engine that wants to combine
+/// the text relevance score with a `monthly_downloads` fast field.
+pub struct TweakedScoreTopCollector<TScoreTweaker>
+where
+    TScoreTweaker: ScoreTweaker<Score>,
+{
+    score_tweaker: TScoreTweaker,
+    heap: TopKHeap<Score>,
+    current_segment: SegmentLocalId,
+    current_tweaker: Option<TScoreTweaker::Child>,
+}
+
+impl<TScoreTweaker> TweakedScoreTopCollector<TScoreTweaker>
+where
+    TScoreTweaker: ScoreTweaker<Score>,
+{
+    /// Creates a `TweakedScoreTopCollector` that keeps the `limit` top
+    /// documents, ranked by `score_tweaker`.
+    pub fn new(
+        score_tweaker: TScoreTweaker,
+        limit: usize,
+    ) -> TweakedScoreTopCollector<TScoreTweaker> {
+        TweakedScoreTopCollector {
+            score_tweaker,
+            heap: TopKHeap::with_limit(limit),
+            current_segment: 0,
+            current_tweaker: None,
+        }
+    }
+
+    /// Returns the top documents, best first, as `(Score, DocAddress)` pairs.
+    pub fn top_docs(self) -> Vec<(Score, DocAddress)> {
+        self.heap.into_top_docs()
+    }
+}
+
+impl<TScoreTweaker> Collector for TweakedScoreTopCollector<TScoreTweaker>
+where
+    TScoreTweaker: ScoreTweaker<Score>,
+{
+    fn set_segment(
+        &mut self,
+        segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> Result<()> {
+        self.current_segment = segment_local_id;
+        self.current_tweaker = Some(self.score_tweaker.for_segment(segment_reader)?);
+        Ok(())
+    }
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let tweaked_score = self
+            .current_tweaker
+            .as_mut()
+            .expect("collect() called before set_segment()")
+            .score(doc, score);
+        self.heap
+            .collect(DocAddress(self.current_segment, doc), tweaked_score);
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+}
+
+/// `CustomScoreTopCollector` ranks documents solely on the value
+/// produced by a `CustomScorer`, ignoring the original BM25 score
+/// entirely. Useful when sorting by a precomputed `crate_score` fast
+/// field rather than text relevance.
+pub struct CustomScoreTopCollector<TCustomScorer>
+where
+    TCustomScorer: CustomScorer<Score>,
+{
+    custom_scorer: TCustomScorer,
+    heap: TopKHeap<Score>,
+    current_segment: SegmentLocalId,
+    current_scorer: Option<TCustomScorer::Child>,
+}
+
+impl<TCustomScorer> CustomScoreTopCollector<TCustomScorer>
+where
+    TCustomScorer: CustomScorer<Score>,
+{
+    /// Creates a `CustomScoreTopCollector` that keeps the `limit` top
+    /// documents, ranked by `custom_scorer`.
+    pub fn new(
+        custom_scorer: TCustomScorer,
+        limit: usize,
+    ) -> CustomScoreTopCollector<TCustomScorer> {
+        CustomScoreTopCollector {
+            custom_scorer,
+            heap: TopKHeap::with_limit(limit),
+            current_segment: 0,
+            current_scorer: None,
+        }
+    }
+
+    /// Returns the top documents, best first, as `(Score, DocAddress)` pairs.
+    pub fn top_docs(self) -> Vec<(Score, DocAddress)> {
+        self.heap.into_top_docs()
+    }
+}
+
+impl<TCustomScorer> Collector for CustomScoreTopCollector<TCustomScorer>
+where
+    TCustomScorer: CustomScorer<Score>,
+{
+    fn set_segment(
+        &mut self,
+        segment_local_id: SegmentLocalId,
+        segment_reader: &SegmentReader,
+    ) -> Result<()> {
+        self.current_segment = segment_local_id;
+        self.current_scorer = Some(self.custom_scorer.for_segment(segment_reader)?);
+        Ok(())
+    }
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        let score = self
+            .current_scorer
+            .as_mut()
+            .expect("collect() called before set_segment()")
+            .score(doc);
+        self.heap
+            .collect(DocAddress(self.current_segment, doc), score);
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CustomScoreTopCollector, FastFieldScoreTweaker, TweakedScoreTopCollector};
+    use query::RegexQuery;
+    use schema::SchemaBuilder;
+    use schema::{FAST, TEXT};
+    use Index;
+
+    #[test]
+    pub fn test_fast_field_score_tweaker_combines_query_score_and_downloads() {
+        let mut schema_builder = SchemaBuilder::new();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let downloads = schema_builder.add_u64_field("downloads", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            index_writer.add_document(doc!(
+                title => "diary",
+                downloads => 1u64,
+            ));
+            index_writer.add_document(doc!(
+                title => "diary",
+                downloads => 1_000u64,
+            ));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+
+        let query = RegexQuery::new("diary".to_string(), title);
+        let score_tweaker = FastFieldScoreTweaker::new(downloads);
+        let mut collector = TweakedScoreTopCollector::new(score_tweaker, 2);
+        searcher.search(&query, &mut collector).unwrap();
+
+        let top_docs = collector.top_docs();
+        assert_eq!(top_docs.len(), 2);
+        let (best_score, _) = top_docs[0];
+        let (worst_score, _) = top_docs[1];
+        assert!(
+            best_score > worst_score,
+            "The document with more downloads should rank first"
+        );
+    }
+
+    #[test]
+    pub fn test_custom_score_top_collector_ignores_bm25() {
+        let mut schema_builder = SchemaBuilder::new();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let downloads = schema_builder.add_u64_field("downloads", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            index_writer.add_document(doc!(
+                title => "diary diary diary",
+                downloads => 1u64,
+            ));
+            index_writer.add_document(doc!(
+                title => "diary",
+                downloads => 1_000u64,
+            ));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+
+        // `RegexQuery` gives both documents the same score of 1.0, even
+        // though the first repeats the term; the custom scorer ranks
+        // purely on `downloads`.
+        let query = RegexQuery::new("diary".to_string(), title);
+        let custom_scorer = FastFieldScoreTweaker::new(downloads);
+        let mut collector = CustomScoreTopCollector::new(custom_scorer, 2);
+        searcher.search(&query, &mut collector).unwrap();
+
+        let top_docs = collector.top_docs();
+        assert_eq!(top_docs.len(), 2);
+        let (best_score, best_doc) = top_docs[0];
+        let (worst_score, worst_doc) = top_docs[1];
+        assert!(best_score > worst_score);
+        assert_eq!(best_doc.1, 1, "The second document has more downloads");
+        assert_eq!(worst_doc.1, 0);
+    }
+}
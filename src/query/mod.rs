@@ -0,0 +1,7 @@
+mod fuzzy_term_query;
+mod regex_query;
+mod wildcard_query;
+
+pub use self::fuzzy_term_query::FuzzyTermQuery;
+pub use self::regex_query::RegexQuery;
+pub use self::wildcard_query::WildcardQuery;
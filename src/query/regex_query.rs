@@ -6,12 +6,9 @@ use std::clone::Clone;
 use Result;
 use Searcher;
 
-// A Regex Query matches all of the documents
+/// A Regex Query matches all of the documents
 /// containing a specific term that matches
 /// a regex pattern
-/// A Fuzzy Query matches all of the documents
-/// containing a specific term that is within
-/// Levenshtein distance
 ///
 /// ```rust
 /// #[macro_use]
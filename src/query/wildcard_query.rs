@@ -0,0 +1,184 @@
+use error::TantivyError;
+use fst_regex::Regex;
+use query::{AutomatonWeight, Query, Weight};
+use schema::Field;
+use std::clone::Clone;
+use Result;
+use Searcher;
+
+/// A `WildcardQuery` matches all of the documents containing a term
+/// matching a shell-style glob pattern: `*` stands for any run of
+/// characters, `?` for a single character.
+///
+/// Patterns are compiled down to an `fst_regex::Regex` and run through
+/// the same `AutomatonWeight` machinery as `RegexQuery`, so users get
+/// glob matching without having to hand-write a regex.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{SchemaBuilder, TEXT};
+/// use tantivy::{Index, Result};
+/// use tantivy::collector::{CountCollector, TopCollector, chain};
+/// use tantivy::query::WildcardQuery;
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = SchemaBuilder::new();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(
+///             title => "The Diary of Muadib",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "A Dairy Cow",
+///         ));
+///         index_writer.commit().unwrap();
+///     }
+///
+///     index.load_searchers()?;
+///     let searcher = index.searcher();
+///
+///     {
+///         let mut top_collector = TopCollector::with_limit(2);
+///         let mut count_collector = CountCollector::default();
+///         {
+///             let mut collectors = chain().push(&mut top_collector).push(&mut count_collector);
+///             let query = WildcardQuery::new("d?ary".to_string(), title);
+///             searcher.search(&query, &mut collectors).unwrap();
+///         }
+///         assert_eq!(count_collector.count(), 1);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WildcardQuery {
+    pattern: String,
+    field: Field,
+}
+
+impl WildcardQuery {
+    /// Creates a new `WildcardQuery` from a glob `pattern`, where `*`
+    /// matches any run of characters and `?` matches a single one.
+    pub fn new(pattern: String, field: Field) -> WildcardQuery {
+        WildcardQuery { pattern, field }
+    }
+
+    fn to_regex_pattern(&self) -> String {
+        let mut regex_pattern = String::with_capacity(self.pattern.len() * 2);
+        for c in self.pattern.chars() {
+            match c {
+                '*' => regex_pattern.push_str(".*"),
+                '?' => regex_pattern.push('.'),
+                _ => {
+                    if is_regex_meta_character(c) {
+                        regex_pattern.push('\\');
+                    }
+                    regex_pattern.push(c);
+                }
+            }
+        }
+        regex_pattern
+    }
+
+    fn specialized_weight(&self) -> Result<AutomatonWeight<Regex>> {
+        if self.pattern.is_empty() {
+            return Err(TantivyError::InvalidArgument(self.pattern.clone()));
+        }
+        let regex_pattern = self.to_regex_pattern();
+        let automaton = Regex::new(&regex_pattern)
+            .map_err(|_| TantivyError::InvalidArgument(self.pattern.clone()))?;
+        Ok(AutomatonWeight::new(self.field, automaton))
+    }
+}
+
+impl Query for WildcardQuery {
+    fn weight(&self, _searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight()?))
+    }
+}
+
+fn is_regex_meta_character(c: char) -> bool {
+    match c {
+        '.' | '+' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' | '\\' => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WildcardQuery;
+    use collector::TopCollector;
+    use schema::SchemaBuilder;
+    use schema::TEXT;
+    use tests::assert_nearly_equals;
+    use Index;
+
+    #[test]
+    pub fn test_wildcard_query() {
+        let mut schema_builder = SchemaBuilder::new();
+        let country_field = schema_builder.add_text_field("country", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            index_writer.add_document(doc!(
+                country_field => "japan",
+            ));
+            index_writer.add_document(doc!(
+                country_field => "korea",
+            ));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        {
+            let mut collector = TopCollector::with_limit(2);
+            let wildcard_query = WildcardQuery::new("jap?n".to_string(), country_field);
+            searcher.search(&wildcard_query, &mut collector).unwrap();
+            let scored_docs = collector.top_docs();
+            assert_eq!(scored_docs.len(), 1, "Expected only 1 document");
+            let (score, _) = scored_docs[0];
+            assert_nearly_equals(1f32, score);
+        }
+        {
+            let mut collector = TopCollector::with_limit(2);
+            let wildcard_query = WildcardQuery::new("jap*".to_string(), country_field);
+            searcher.search(&wildcard_query, &mut collector).unwrap();
+            let scored_docs = collector.top_docs();
+            assert_eq!(scored_docs.len(), 1, "Expected only 1 document");
+        }
+        {
+            let mut collector = TopCollector::with_limit(2);
+            let wildcard_query = WildcardQuery::new("kor?a".to_string(), country_field);
+            searcher.search(&wildcard_query, &mut collector).unwrap();
+            let scored_docs = collector.top_docs();
+            assert_eq!(scored_docs.len(), 1, "Expected only 1 document");
+        }
+    }
+
+    #[test]
+    pub fn test_wildcard_query_with_multibyte_literal() {
+        let mut schema_builder = SchemaBuilder::new();
+        let country_field = schema_builder.add_text_field("country", TEXT);
+        let schema = schema_builder.build();
+        let _index = Index::create_in_ram(schema);
+        let wildcard_query = WildcardQuery::new("café*".to_string(), country_field);
+        assert!(wildcard_query.specialized_weight().is_ok());
+    }
+
+    #[test]
+    pub fn test_wildcard_query_empty_pattern_is_invalid_argument() {
+        let mut schema_builder = SchemaBuilder::new();
+        let country_field = schema_builder.add_text_field("country", TEXT);
+        let schema = schema_builder.build();
+        let _index = Index::create_in_ram(schema);
+        let wildcard_query = WildcardQuery::new("".to_string(), country_field);
+        assert!(wildcard_query.specialized_weight().is_err());
+    }
+}
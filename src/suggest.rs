@@ -0,0 +1,117 @@
+use levenshtein_automata::LevenshteinAutomatonBuilder;
+use schema::Field;
+use std::collections::HashMap;
+use Result;
+use Searcher;
+
+/// Maximum edit distance considered when looking for "did you mean"
+/// suggestions. Terms are tried at distance 1 first, and only if that
+/// yields fewer than `limit` candidates do we fall back to distance 2: this mirrors how a
+/// human reader tolerates one typo readily but two only grudgingly.
+const MAX_EDIT_DISTANCE: u8 = 2;
+
+impl Searcher {
+    /// Suggests up to `limit` in-vocabulary terms for `field` that are
+    /// spelling-close to `term`, ranked by edit distance first and
+    /// document frequency second.
+    ///
+    /// This streams the field's FST term dictionary through a bounded
+    /// Levenshtein automaton (the same construction used by
+    /// `FuzzyTermQuery`) at distance 1, then distance 2 if not enough
+    /// candidates were found, so applications can offer spelling
+    /// correction without maintaining a separate index. A term found
+    /// in several segments is reported once, with its document
+    /// frequencies summed across segments.
+    ///
+    /// ```rust,ignore
+    /// let suggestions = searcher.suggest_terms(title, "levenshtien", 5)?;
+    /// ```
+    pub fn suggest_terms(
+        &self,
+        field: Field,
+        term: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, u64)>> {
+        let mut candidates: HashMap<String, (u8, u64)> = HashMap::new();
+        for distance in 1..=MAX_EDIT_DISTANCE {
+            let builder = LevenshteinAutomatonBuilder::new(distance, true);
+            let automaton = builder.build_dfa(term);
+            let mut doc_freq_by_term: HashMap<String, u64> = HashMap::new();
+            for segment_reader in self.segment_readers() {
+                let inverted_index = segment_reader.inverted_index(field);
+                let term_dict = inverted_index.terms();
+                let mut term_stream = term_dict.search(automaton.clone()).into_stream()?;
+                while let Some((term_bytes, term_info)) = term_stream.next() {
+                    if term_bytes == term.as_bytes() {
+                        // Not a misspelling of itself.
+                        continue;
+                    }
+                    let candidate = String::from_utf8_lossy(term_bytes).into_owned();
+                    *doc_freq_by_term.entry(candidate).or_insert(0) +=
+                        u64::from(term_info.doc_freq);
+                }
+            }
+            // A term already found at a smaller distance keeps that
+            // distance and its already-summed doc frequency: the
+            // distance-2 pass re-discovers every distance-1 term, and
+            // without this guard it would double count it.
+            for (candidate, doc_freq) in doc_freq_by_term {
+                candidates.entry(candidate).or_insert((distance, doc_freq));
+            }
+            if candidates.len() >= limit {
+                break;
+            }
+        }
+        let mut ranked: Vec<(String, u8, u64)> = candidates
+            .into_iter()
+            .map(|(term, (distance, doc_freq))| (term, distance, doc_freq))
+            .collect();
+        ranked.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| b.2.cmp(&a.2)));
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .map(|(term, _distance, doc_freq)| (term, doc_freq))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use schema::SchemaBuilder;
+    use schema::TEXT;
+    use Index;
+
+    #[test]
+    pub fn test_suggest_terms_merges_across_segments() {
+        let mut schema_builder = SchemaBuilder::new();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            // Two commits with no merge in between: the term "diary"
+            // ends up split across two segments.
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            index_writer.add_document(doc!(
+                title => "diary",
+            ));
+            index_writer.commit().unwrap();
+            index_writer.add_document(doc!(
+                title => "diary",
+            ));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let suggestions = searcher.suggest_terms(title, "diarys", 5).unwrap();
+        assert_eq!(
+            suggestions.len(),
+            1,
+            "Expected the term to be merged into a single suggestion"
+        );
+        assert_eq!(suggestions[0].0, "diary");
+        assert_eq!(
+            suggestions[0].1, 2,
+            "Expected doc frequency summed across both segments"
+        );
+    }
+}